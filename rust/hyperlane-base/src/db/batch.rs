@@ -0,0 +1,181 @@
+use std::collections::HashMap;
+
+use hyperlane_core::{Decode, Encode};
+use rocksdb::WriteBatch as RocksWriteBatch;
+
+use super::{Result, DB};
+
+/// A staged operation against a single fully-prefixed key.
+enum Op {
+    Put(Vec<u8>),
+    Delete,
+}
+
+/// An in-memory overlay of staged writes and deletes that are flushed to the
+/// underlying rocksdb atomically on [`WriteBatch::commit`].
+///
+/// Reads made through the batch see its pending operations before they are
+/// committed, so agent code can stage every DB update derived from a block of
+/// messages and commit them as a unit — the store is never left half-updated
+/// if the process crashes mid-block.
+pub struct WriteBatch<'a> {
+    db: &'a DB,
+    ops: HashMap<Vec<u8>, Op>,
+}
+
+impl<'a> WriteBatch<'a> {
+    pub(super) fn new(db: &'a DB) -> Self {
+        Self {
+            db,
+            ops: HashMap::new(),
+        }
+    }
+
+    /// Prefix a key and stage a write.
+    fn prefix_store(
+        &mut self,
+        prefix: impl AsRef<[u8]>,
+        key: impl AsRef<[u8]>,
+        value: impl AsRef<[u8]>,
+    ) {
+        self.ops
+            .insert(prefixed(prefix, key), Op::Put(value.as_ref().to_vec()));
+    }
+
+    /// Stage an encodeable write.
+    pub fn store_encodable<V: Encode>(
+        &mut self,
+        prefix: impl AsRef<[u8]>,
+        key: impl AsRef<[u8]>,
+        value: &V,
+    ) {
+        self.prefix_store(prefix, key, value.to_vec());
+    }
+
+    /// Stage an encodeable write under an encodeable key.
+    pub fn store_keyed_encodable<K: Encode, V: Encode>(
+        &mut self,
+        prefix: impl AsRef<[u8]>,
+        key: &K,
+        value: &V,
+    ) {
+        self.store_encodable(prefix, key.to_vec(), value);
+    }
+
+    /// Stage a delete.
+    pub fn delete(&mut self, prefix: impl AsRef<[u8]>, key: impl AsRef<[u8]>) {
+        self.ops.insert(prefixed(prefix, key), Op::Delete);
+    }
+
+    /// Stage a delete under an encodeable key.
+    pub fn delete_keyed<K: Encode>(&mut self, prefix: impl AsRef<[u8]>, key: &K) {
+        self.delete(prefix, key.to_vec());
+    }
+
+    /// Retrieve and attempt to decode, seeing pending writes in this batch
+    /// before falling back to the committed store.
+    pub fn retrieve_decodable<V: Decode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+        key: impl AsRef<[u8]>,
+    ) -> Result<Option<V>> {
+        let full_key = prefixed(prefix, key);
+        let raw = match self.ops.get(&full_key) {
+            Some(Op::Put(value)) => Some(value.clone()),
+            Some(Op::Delete) => None,
+            None => self.db._retrieve(&full_key)?,
+        };
+        Ok(raw
+            .map(|val| V::read_from(&mut val.as_slice()))
+            .transpose()?)
+    }
+
+    /// Retrieve and attempt to decode under an encodeable key.
+    pub fn retrieve_keyed_decodable<K: Encode, V: Decode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+        key: &K,
+    ) -> Result<Option<V>> {
+        self.retrieve_decodable(prefix, key.to_vec())
+    }
+
+    /// Flush every staged operation atomically and return the number applied.
+    pub fn commit(self) -> Result<usize> {
+        let count = self.ops.len();
+        let mut batch = RocksWriteBatch::default();
+        for (key, op) in &self.ops {
+            match op {
+                Op::Put(value) => batch.put(key, value),
+                Op::Delete => batch.delete(key),
+            }
+        }
+        self.db.0.write(batch)?;
+        Ok(count)
+    }
+
+    /// Drop the batch without applying any of its operations.
+    pub fn discard(self) {}
+}
+
+/// Concatenate a prefix and key into a single storage key.
+fn prefixed(prefix: impl AsRef<[u8]>, key: impl AsRef<[u8]>) -> Vec<u8> {
+    let mut buf = Vec::with_capacity(prefix.as_ref().len() + key.as_ref().len());
+    buf.extend(prefix.as_ref());
+    buf.extend(key.as_ref());
+    buf
+}
+
+#[cfg(test)]
+mod test {
+    use crate::db::test_utils;
+
+    #[test]
+    fn reads_see_pending_writes_before_commit() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        let mut batch = db.batch();
+        batch.store_encodable(b"p", b"k", &9u32);
+
+        // Visible through the overlay, but not yet in the committed store.
+        let staged: Option<u32> = batch.retrieve_decodable(b"p", b"k").unwrap();
+        assert_eq!(staged, Some(9u32));
+        let committed: Option<u32> = db.retrieve_decodable(b"p", b"k").unwrap();
+        assert_eq!(committed, None);
+    }
+
+    #[test]
+    fn pending_delete_shadows_committed_value() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        db.store_encodable(b"p", b"k", &1u32).unwrap();
+
+        let mut batch = db.batch();
+        batch.delete(b"p", b"k");
+        let shadowed: Option<u32> = batch.retrieve_decodable(b"p", b"k").unwrap();
+        assert_eq!(shadowed, None);
+    }
+
+    #[test]
+    fn commit_flushes_atomically_and_counts_ops() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        db.store_encodable(b"p", b"gone", &1u32).unwrap();
+
+        let mut batch = db.batch();
+        batch.store_encodable(b"p", b"a", &1u32);
+        batch.store_encodable(b"p", b"b", &2u32);
+        batch.delete(b"p", b"gone");
+        let applied = batch.commit().unwrap();
+        assert_eq!(applied, 3);
+
+        assert_eq!(db.retrieve_decodable::<u32>(b"p", b"a").unwrap(), Some(1u32));
+        assert_eq!(db.retrieve_decodable::<u32>(b"p", b"b").unwrap(), Some(2u32));
+        assert_eq!(db.retrieve_decodable::<u32>(b"p", b"gone").unwrap(), None);
+    }
+
+    #[test]
+    fn discard_applies_nothing() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        let mut batch = db.batch();
+        batch.store_encodable(b"p", b"k", &5u32);
+        batch.discard();
+        assert_eq!(db.retrieve_decodable::<u32>(b"p", b"k").unwrap(), None);
+    }
+}
@@ -0,0 +1,175 @@
+use super::{DbError, Result, DB};
+
+/// Reserved key under which the on-disk schema version is persisted.
+const SCHEMA_VERSION_KEY: &[u8] = b"__schema_version";
+
+/// Schema version this build of the crate expects on disk.
+///
+/// Bump this whenever the layout of a stored type or the `prefix_store`
+/// scheme changes, and add a [`Migration`] bridging the previous version to
+/// the new one in [`migrations`].
+pub const CURRENT_SCHEMA_VERSION: u32 = 0;
+
+/// A single ordered migration step that upgrades the on-disk layout from one
+/// schema version to the next.
+///
+/// A step typically streams old entries with `prefix_iterator`, re-encodes
+/// them under the new prefix/format via `store_encodable`, and deletes the
+/// stale keys.
+pub struct Migration {
+    /// Version this step upgrades from.
+    pub from: u32,
+    /// Version this step produces.
+    pub to: u32,
+    /// The transformation applied to the DB.
+    run: Box<dyn Fn(&DB) -> Result<()>>,
+}
+
+impl Migration {
+    /// Build a migration from `from` to `to` that runs `f`.
+    pub fn new<F>(from: u32, to: u32, f: F) -> Self
+    where
+        F: Fn(&DB) -> Result<()> + 'static,
+    {
+        Self {
+            from,
+            to,
+            run: Box::new(f),
+        }
+    }
+}
+
+/// The ordered registry of migration steps, lowest `from` first.
+///
+/// Empty until the on-disk layout first changes; new steps are appended as
+/// [`CURRENT_SCHEMA_VERSION`] is bumped.
+pub fn migrations() -> Vec<Migration> {
+    vec![]
+}
+
+impl DB {
+    /// Read the persisted schema version, defaulting to `0` for legacy DBs
+    /// that predate the migration framework.
+    pub fn schema_version(&self) -> Result<u32> {
+        Ok(self
+            ._retrieve(SCHEMA_VERSION_KEY)?
+            .and_then(|raw| raw.as_slice().try_into().ok().map(u32::from_be_bytes))
+            .unwrap_or(0))
+    }
+
+    /// Persist the schema version.
+    fn set_schema_version(&self, version: u32) -> Result<()> {
+        self._store(SCHEMA_VERSION_KEY, version.to_be_bytes())
+    }
+
+    /// Run the given migration chain in order, persisting the version after
+    /// each successful step.
+    ///
+    /// A failure mid-chain leaves the counter at the last completed step, so
+    /// the migration can be resumed on the next open. Errors if there is no
+    /// step bridging the current on-disk version up to
+    /// [`CURRENT_SCHEMA_VERSION`].
+    pub fn run_migrations(&self, migrations: &[Migration]) -> Result<()> {
+        self.run_migrations_to(migrations, CURRENT_SCHEMA_VERSION)
+    }
+
+    /// Run the migration chain up to `target`, persisting the version after each
+    /// successful step. Split out from [`DB::run_migrations`] so the success
+    /// path can be exercised against an arbitrary target in tests without
+    /// bumping the compile-time [`CURRENT_SCHEMA_VERSION`].
+    fn run_migrations_to(&self, migrations: &[Migration], target: u32) -> Result<()> {
+        let mut version = self.schema_version()?;
+
+        for migration in migrations {
+            if migration.from != version {
+                continue;
+            }
+            (migration.run)(self).map_err(|e| {
+                DbError::MigrationError(format!(
+                    "step {}->{} failed: {e}",
+                    migration.from, migration.to
+                ))
+            })?;
+            self.set_schema_version(migration.to)?;
+            version = migration.to;
+        }
+
+        if version != target {
+            return Err(DbError::MigrationError(format!(
+                "no migration path from on-disk version {version} to {target}"
+            )));
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::test_utils;
+
+    #[test]
+    fn fresh_db_defaults_to_version_zero() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        assert_eq!(db.schema_version().unwrap(), 0);
+    }
+
+    #[test]
+    fn empty_chain_is_a_no_op_at_current_version() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        // `CURRENT_SCHEMA_VERSION` is 0 in this build, so a fresh db needs no
+        // steps and `run_migrations` succeeds without touching the counter.
+        db.run_migrations(&[]).unwrap();
+        assert_eq!(db.schema_version().unwrap(), CURRENT_SCHEMA_VERSION);
+    }
+
+    #[test]
+    fn step_re_encodes_data_and_bumps_to_target() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        db.store_encodable(b"old/", b"k", &1u32).unwrap();
+
+        let chain = vec![Migration::new(0, 1, |db| {
+            // Re-encode every `old/` entry under `new/` then drop it.
+            let moves: Vec<_> = db.prefix_iterator(b"old/").map(|res| res.unwrap()).collect();
+            for (key, value) in moves {
+                let suffix = &key[b"old/".len()..];
+                db.prefix_store(b"new/", suffix, &value)?;
+                db._delete(&key)?;
+            }
+            Ok(())
+        })];
+
+        // Drive the success path against a target of 1 (the compile-time
+        // CURRENT_SCHEMA_VERSION is still 0, so this stands in for a future bump).
+        db.run_migrations_to(&chain, 1).unwrap();
+        assert_eq!(db.schema_version().unwrap(), 1);
+        let moved: Option<u32> = db.retrieve_decodable(b"new/", b"k").unwrap();
+        assert_eq!(moved, Some(1u32));
+    }
+
+    #[test]
+    fn missing_path_to_target_errors() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        // No step bridges 0 -> 1, so reaching the target fails.
+        assert!(db.run_migrations_to(&[], 1).is_err());
+        assert_eq!(db.schema_version().unwrap(), 0);
+    }
+
+    #[test]
+    fn failure_mid_chain_leaves_version_at_last_completed_step() {
+        let (_dir, db) = test_utils::setup_db_temp();
+
+        let chain = vec![
+            Migration::new(0, 1, |_db| Ok(())),
+            Migration::new(1, 2, |_db| {
+                Err(DbError::MigrationError("boom".into()))
+            }),
+        ];
+
+        assert!(db.run_migrations_to(&chain, 2).is_err());
+        // Step 0->1 committed, step 1->2 failed, so the counter stays at 1 and
+        // the chain can be resumed on the next open.
+        assert_eq!(db.schema_version().unwrap(), 1);
+    }
+}
@@ -0,0 +1,190 @@
+use hyperlane_core::{Decode, Encode};
+use rocksdb::WriteBatch;
+
+use super::{DbError, Result, DB};
+
+/// Reserved prefix for the time-to-live side index. Index keys are
+/// `TTL_INDEX_PREFIX || expiry_be_u64 || full_key`, so iterating the index
+/// yields entries in ascending expiry order.
+const TTL_INDEX_PREFIX: &[u8] = b"__ttl_index/";
+
+/// Reserved prefix for the reverse owner index, which records the *current*
+/// expiry for each TTL-stamped key (`TTL_OWNER_PREFIX || full_key ->
+/// expiry_be_u64`). It lets [`DB::store_encodable_with_ttl`] supersede a prior
+/// expiry and lets [`DB::vacuum_expired`] skip stale forward entries whose key
+/// has since been re-stamped.
+const TTL_OWNER_PREFIX: &[u8] = b"__ttl_owner/";
+
+impl DB {
+    /// Delete every entry under `prefix` whose decoded value satisfies
+    /// `predicate`, batching the deletes for efficiency.
+    ///
+    /// Returns the number of keys removed. Used to cap storage for transient
+    /// data such as processed-message markers older than a retention window.
+    pub fn prune_prefix<V, F>(&self, prefix: impl AsRef<[u8]>, mut predicate: F) -> Result<usize>
+    where
+        V: Decode,
+        F: FnMut(&V) -> bool,
+    {
+        let prefix = prefix.as_ref();
+        let mut batch = WriteBatch::default();
+        let mut removed = 0;
+
+        for res in self.0.prefix_iterator(prefix) {
+            let (key, value) = res.map_err(|e| DbError::PruneError(e.to_string()))?;
+            if !key.starts_with(prefix) {
+                break;
+            }
+            let decoded =
+                V::read_from(&mut value.as_ref()).map_err(|e| DbError::PruneError(e.to_string()))?;
+            if predicate(&decoded) {
+                batch.delete(&key);
+                removed += 1;
+            }
+        }
+
+        self.0
+            .write(batch)
+            .map_err(|e| DbError::PruneError(e.to_string()))?;
+        Ok(removed)
+    }
+
+    /// Store an encodeable value and stamp it with an `expiry` timestamp in the
+    /// TTL side index so [`DB::vacuum_expired`] can reclaim it later.
+    ///
+    /// Re-stamping a key supersedes its previous expiry: the old forward-index
+    /// entry is removed so only the latest expiry governs the key. Note that a
+    /// plain [`DB::store_encodable`] overwrite does *not* clear an existing TTL
+    /// stamp — callers should re-stamp through this method (or delete the key)
+    /// when replacing a TTL-stamped value.
+    pub fn store_encodable_with_ttl<V: Encode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+        key: impl AsRef<[u8]>,
+        value: &V,
+        expiry: u64,
+    ) -> Result<()> {
+        self.store_encodable(&prefix, &key, value)?;
+
+        let full_key = [prefix.as_ref(), key.as_ref()].concat();
+        let owner_key = [TTL_OWNER_PREFIX, full_key.as_slice()].concat();
+
+        // Drop the forward entry for any previous expiry so a re-stamp wins.
+        if let Some(prev) = self._retrieve(&owner_key)? {
+            if let Ok(prev_expiry) = <[u8; 8]>::try_from(prev.as_slice()) {
+                self._delete(
+                    [TTL_INDEX_PREFIX, prev_expiry.as_slice(), full_key.as_slice()].concat(),
+                )?;
+            }
+        }
+
+        self._store(
+            [TTL_INDEX_PREFIX, expiry.to_be_bytes().as_slice(), full_key.as_slice()].concat(),
+            &full_key,
+        )?;
+        self._store(owner_key, expiry.to_be_bytes())
+    }
+
+    /// Delete every TTL-stamped entry whose expiry is at or before `now`, then
+    /// compact the affected key range to reclaim space.
+    ///
+    /// Returns the number of entries swept.
+    pub fn vacuum_expired(&self, now: u64) -> Result<usize> {
+        let mut batch = WriteBatch::default();
+        let mut removed = 0;
+        let mut affected: Vec<Vec<u8>> = vec![];
+
+        for res in self.0.prefix_iterator(TTL_INDEX_PREFIX) {
+            let (index_key, full_key) = res.map_err(|e| DbError::PruneError(e.to_string()))?;
+            if !index_key.starts_with(TTL_INDEX_PREFIX) {
+                break;
+            }
+            // `starts_with` only guarantees the prefix is present, not the
+            // trailing 8-byte expiry, so slice with a checked `get`.
+            let expiry_bytes: [u8; 8] = index_key
+                .get(TTL_INDEX_PREFIX.len()..TTL_INDEX_PREFIX.len() + 8)
+                .and_then(|s| s.try_into().ok())
+                .ok_or_else(|| DbError::PruneError("malformed TTL index key".into()))?;
+            // Index is ordered by expiry, so the first future entry ends the sweep.
+            if u64::from_be_bytes(expiry_bytes) > now {
+                break;
+            }
+
+            // Always drop the forward entry we just consumed.
+            batch.delete(&index_key);
+
+            // Only delete the value if this entry still reflects the key's
+            // current expiry; a superseded entry (the key was re-stamped with a
+            // later expiry) leaves the live value untouched.
+            let owner_key = [TTL_OWNER_PREFIX, full_key.as_ref()].concat();
+            let is_current = self
+                ._retrieve(&owner_key)?
+                .map(|cur| cur.as_slice() == expiry_bytes.as_slice())
+                .unwrap_or(false);
+            if is_current {
+                batch.delete(&full_key);
+                batch.delete(&owner_key);
+                affected.push(full_key.to_vec());
+                removed += 1;
+            }
+        }
+
+        self.0
+            .write(batch)
+            .map_err(|e| DbError::PruneError(e.to_string()))?;
+
+        if let (Some(lo), Some(hi)) = (affected.iter().min(), affected.iter().max()) {
+            self.0.compact_range(Some(lo.as_slice()), Some(hi.as_slice()));
+        }
+
+        Ok(removed)
+    }
+}
+
+#[cfg(test)]
+mod test {
+    use crate::db::test_utils;
+
+    #[test]
+    fn prune_prefix_removes_matching_entries() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        for (k, v) in [(b"a", 1u32), (b"b", 2u32), (b"c", 3u32)] {
+            db.store_encodable(b"p", k, &v).unwrap();
+        }
+
+        let removed = db.prune_prefix::<u32, _>(b"p", |v| *v % 2 == 1).unwrap();
+        assert_eq!(removed, 2);
+        assert_eq!(db.retrieve_decodable::<u32>(b"p", b"b").unwrap(), Some(2));
+        assert_eq!(db.retrieve_decodable::<u32>(b"p", b"a").unwrap(), None);
+    }
+
+    #[test]
+    fn vacuum_expired_sweeps_only_past_expiries() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        db.store_encodable_with_ttl(b"p", b"a", &1u32, 100).unwrap();
+        db.store_encodable_with_ttl(b"p", b"b", &2u32, 200).unwrap();
+
+        let removed = db.vacuum_expired(150).unwrap();
+        assert_eq!(removed, 1);
+        assert_eq!(db.retrieve_decodable::<u32>(b"p", b"a").unwrap(), None);
+        assert_eq!(db.retrieve_decodable::<u32>(b"p", b"b").unwrap(), Some(2));
+    }
+
+    #[test]
+    fn re_stamp_supersedes_prior_expiry() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        db.store_encodable_with_ttl(b"p", b"a", &1u32, 100).unwrap();
+        // Re-stamp with a later expiry and a fresh value.
+        db.store_encodable_with_ttl(b"p", b"a", &2u32, 300).unwrap();
+
+        // The original expiry has passed, but the key was superseded, so the
+        // current value must survive.
+        let removed = db.vacuum_expired(150).unwrap();
+        assert_eq!(removed, 0);
+        assert_eq!(db.retrieve_decodable::<u32>(b"p", b"a").unwrap(), Some(2));
+
+        // Once the new expiry passes, it is swept.
+        assert_eq!(db.vacuum_expired(300).unwrap(), 1);
+        assert_eq!(db.retrieve_decodable::<u32>(b"p", b"a").unwrap(), None);
+    }
+}
@@ -0,0 +1,195 @@
+use hyperlane_core::{Decode, Encode};
+use rocksdb::{Direction, IteratorMode};
+
+use super::{Result, DB};
+
+/// Encode an integer key component in fixed-width big-endian form so that
+/// RocksDB's lexicographic byte ordering matches numeric ordering.
+///
+/// The protocol's default `Encode` does not guarantee this, so numeric keys
+/// (message nonces, block heights, ...) must go through this layer to be
+/// range-scannable with [`DB::range_iterator`].
+pub trait OrderedKey {
+    /// The big-endian fixed-width byte encoding of `self`.
+    fn to_ordered_key(&self) -> Vec<u8>;
+}
+
+macro_rules! impl_ordered_key {
+    ($($t:ty),*) => {
+        $(
+            impl OrderedKey for $t {
+                fn to_ordered_key(&self) -> Vec<u8> {
+                    self.to_be_bytes().to_vec()
+                }
+            }
+        )*
+    };
+}
+
+impl_ordered_key!(u8, u16, u32, u64, u128);
+
+impl DB {
+    /// Store a value under a key serialized with [`OrderedKey`] so that
+    /// RocksDB's byte ordering matches the key's numeric ordering.
+    ///
+    /// Writes must go through this (rather than `store_keyed_encodable`, which
+    /// uses the protocol's default `Encode`) for [`DB::range_iterator`] and
+    /// [`DB::last_for_prefix`] to yield entries in numeric order.
+    pub fn store_ordered_keyed_encodable<K: OrderedKey, V: Encode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+        key: &K,
+        value: &V,
+    ) -> Result<()> {
+        self.store_encodable(prefix, key.to_ordered_key(), value)
+    }
+
+    /// Retrieve a value stored with [`DB::store_ordered_keyed_encodable`].
+    pub fn retrieve_ordered_keyed_decodable<K: OrderedKey, V: Decode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+        key: &K,
+    ) -> Result<Option<V>> {
+        self.retrieve_decodable(prefix, key.to_ordered_key())
+    }
+
+    /// Iterate decoded entries whose key lies in `[prefix||start, prefix||end]`.
+    ///
+    /// Seeks directly to `prefix||start` and yields entries in ascending key
+    /// order until `prefix||end` is passed, enabling efficient range queries
+    /// such as "messages with nonce >= N" when keys are encoded with
+    /// [`OrderedKey`]. Each item is the key suffix (the bytes after `prefix`)
+    /// paired with the decoded value.
+    pub fn range_iterator<'a, V: Decode>(
+        &'a self,
+        prefix: impl AsRef<[u8]>,
+        start_key: impl AsRef<[u8]>,
+        end_key: impl AsRef<[u8]>,
+    ) -> impl Iterator<Item = Result<(Vec<u8>, V)>> + 'a {
+        let prefix_len = prefix.as_ref().len();
+        let start = [prefix.as_ref(), start_key.as_ref()].concat();
+        let end = [prefix.as_ref(), end_key.as_ref()].concat();
+
+        self.0
+            .iterator(IteratorMode::From(&start, Direction::Forward))
+            .take_while(move |res| match res {
+                Ok((key, _)) => key.as_ref() <= end.as_slice(),
+                Err(_) => true,
+            })
+            .map(move |res| {
+                let (key, value) = res?;
+                let suffix = key[prefix_len..].to_vec();
+                Ok((suffix, V::read_from(&mut value.as_ref())?))
+            })
+    }
+
+    /// Return the highest-keyed entry under `prefix`, if any.
+    ///
+    /// Seeks to the prefix upper bound and steps backward, so it does not load
+    /// the whole prefix — useful for "latest processed checkpoint" lookups.
+    /// The key suffix (bytes after `prefix`) is returned alongside the decoded
+    /// value.
+    pub fn last_for_prefix<V: Decode>(
+        &self,
+        prefix: impl AsRef<[u8]>,
+    ) -> Result<Option<(Vec<u8>, V)>> {
+        let prefix = prefix.as_ref();
+        let upper = next_prefix(prefix);
+        let mode = match &upper {
+            // `From(bound, Reverse)` is a `seek_for_prev`, which lands on the
+            // largest key <= bound. If a key *equal* to `bound` exists under a
+            // later prefix it lands there, so we must step back past every key
+            // >= bound before inspecting the first real candidate.
+            Some(bound) => IteratorMode::From(bound, Direction::Reverse),
+            None => IteratorMode::End,
+        };
+
+        for res in self.0.iterator(mode) {
+            let (key, value) = res?;
+            // Skip keys that belong to a later prefix (only possible on the
+            // first step, when the seek overshot onto `bound` itself).
+            if let Some(bound) = &upper {
+                if key.as_ref() >= bound.as_slice() {
+                    continue;
+                }
+            }
+            // The first key below the bound is the greatest key in the DB that
+            // is < bound. If it carries our prefix it is the answer; otherwise
+            // no entry under `prefix` exists.
+            if !key.starts_with(prefix) {
+                return Ok(None);
+            }
+            let suffix = key[prefix.len()..].to_vec();
+            return Ok(Some((suffix, V::read_from(&mut value.as_ref())?)));
+        }
+
+        Ok(None)
+    }
+}
+
+/// Smallest key strictly greater than every key sharing `prefix`, obtained by
+/// incrementing the last non-`0xff` byte. Returns `None` when no such bound
+/// exists (empty or all-`0xff` prefix), in which case the caller should seek
+/// from the end of the DB.
+fn next_prefix(prefix: &[u8]) -> Option<Vec<u8>> {
+    let mut bound = prefix.to_vec();
+    while let Some(last) = bound.last_mut() {
+        if *last < 0xff {
+            *last += 1;
+            return Some(bound);
+        }
+        bound.pop();
+    }
+    None
+}
+
+#[cfg(test)]
+mod test {
+    use super::*;
+    use crate::db::test_utils;
+
+    #[test]
+    fn ordered_keys_iterate_in_numeric_order() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        // Insert out of order; big-endian keys must still scan ascending.
+        for nonce in [255u32, 1, 256, 2] {
+            db.store_ordered_keyed_encodable(b"n", &nonce, &nonce).unwrap();
+        }
+
+        let got: Vec<u32> = db
+            .range_iterator::<u32>(b"n", 2u32.to_ordered_key(), 256u32.to_ordered_key())
+            .map(|res| res.unwrap().1)
+            .collect();
+        assert_eq!(got, vec![2, 255, 256]);
+    }
+
+    #[test]
+    fn last_for_prefix_returns_highest_key() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        for nonce in [1u32, 7, 3] {
+            db.store_ordered_keyed_encodable(b"n", &nonce, &nonce).unwrap();
+        }
+        let (_, value): (_, u32) = db.last_for_prefix::<u32>(b"n").unwrap().unwrap();
+        assert_eq!(value, 7);
+    }
+
+    #[test]
+    fn last_for_prefix_ignores_adjacent_prefix_key() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        // Prefix `[1]` holds our data; a key equal to the upper bound `[2]`
+        // lives under a different prefix. The reverse seek must step past it
+        // instead of returning None.
+        db.store_encodable([1u8], [0u8], &42u32).unwrap();
+        db.store_encodable([2u8], [], &99u32).unwrap();
+
+        let (_, value): (_, u32) = db.last_for_prefix::<u32>([1u8]).unwrap().unwrap();
+        assert_eq!(value, 42);
+    }
+
+    #[test]
+    fn last_for_prefix_none_when_absent() {
+        let (_dir, db) = test_utils::setup_db_temp();
+        db.store_encodable([2u8], [], &99u32).unwrap();
+        assert!(db.last_for_prefix::<u32>([1u8]).unwrap().is_none());
+    }
+}
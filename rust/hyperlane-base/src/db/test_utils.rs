@@ -0,0 +1,17 @@
+use tempfile::TempDir;
+
+use super::DB;
+
+/// Open a clean db rooted at `db_path` for use in tests.
+pub fn setup_db(db_path: String) -> DB {
+    DB::from_path(std::path::Path::new(&db_path)).expect("Failed to open db path")
+}
+
+/// Open a clean db in a fresh temporary directory, returning the [`TempDir`]
+/// guard alongside it. Bind the guard for the duration of the test so the
+/// backing directory is removed on drop rather than leaked.
+pub fn setup_db_temp() -> (TempDir, DB) {
+    let dir = tempfile::tempdir().expect("Failed to create temp dir");
+    let db = setup_db(dir.path().join("db").to_str().unwrap().into());
+    (dir, db)
+}